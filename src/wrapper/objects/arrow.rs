@@ -0,0 +1,214 @@
+//! Export of JNI primitive arrays through the [Arrow C Data
+//! Interface](https://arrow.apache.org/docs/format/CDataInterface.html), so that a
+//! Java-side primitive array can be consumed by any Arrow implementation without an
+//! additional copy.
+
+use log::error;
+
+use crate::objects::auto_array::AutoArray;
+use crate::objects::release_mode::ReleaseMode;
+use crate::objects::type_array::TypeArray;
+use crate::objects::GlobalRef;
+use crate::{errors::*, JavaVM};
+use std::ffi::c_void;
+use std::os::raw::c_char;
+use std::ptr;
+use std::ptr::NonNull;
+
+/// The subset of Arrow primitive types that [`AutoArray`] knows how to export.
+///
+/// Implemented for the JNI primitive types that have a matching fixed-width Arrow
+/// type: `jbyte`, `jshort`, `jint`, `jlong`, `jfloat`, and `jdouble`.
+pub trait ArrowPrimitive: TypeArray {
+    /// The Arrow `format` string for this type, as defined by the C Data Interface.
+    const ARROW_FORMAT: &'static str;
+}
+
+macro_rules! arrow_primitive {
+    ($jni_type:ty, $format:expr) => {
+        impl ArrowPrimitive for $jni_type {
+            const ARROW_FORMAT: &'static str = $format;
+        }
+    };
+}
+
+arrow_primitive!(crate::sys::jbyte, "c");
+arrow_primitive!(crate::sys::jshort, "s");
+arrow_primitive!(crate::sys::jint, "i");
+arrow_primitive!(crate::sys::jlong, "l");
+arrow_primitive!(crate::sys::jfloat, "f");
+arrow_primitive!(crate::sys::jdouble, "g");
+
+/// C ABI struct describing the type of an [`ArrowArray`], per the Arrow C Data
+/// Interface.
+#[repr(C)]
+pub struct ArrowSchema {
+    pub format: *const c_char,
+    pub name: *const c_char,
+    pub metadata: *const c_char,
+    pub flags: i64,
+    pub n_children: i64,
+    pub children: *mut *mut ArrowSchema,
+    pub dictionary: *mut ArrowSchema,
+    pub release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    pub private_data: *mut c_void,
+}
+
+/// C ABI struct describing the data of an Arrow array, per the Arrow C Data
+/// Interface.
+#[repr(C)]
+pub struct ArrowArray {
+    pub length: i64,
+    pub null_count: i64,
+    pub offset: i64,
+    pub n_buffers: i64,
+    pub n_children: i64,
+    pub buffers: *mut *const c_void,
+    pub children: *mut *mut ArrowArray,
+    pub dictionary: *mut ArrowArray,
+    pub release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    pub private_data: *mut c_void,
+}
+
+/// Everything needed to release the exported array once the Arrow consumer is done
+/// with it, independent of the JNI local references and `JNIEnv` borrow that were
+/// current when it was exported.
+///
+/// `obj` is promoted to a [`GlobalRef`] (rather than kept as the original local
+/// reference) because the Arrow consumer may call `release` after the native method
+/// that created this export has already returned, at which point local references are
+/// invalidated. `vm` lets `release` reattach the calling thread to the JVM and obtain
+/// a fresh `JNIEnv`, since the consumer may also call `release` from a thread that was
+/// never attached (or from the wrong thread) rather than the one that exported it.
+struct ExportedArray<T: ArrowPrimitive> {
+    global: GlobalRef,
+    vm: JavaVM,
+    ptr: NonNull<T>,
+    mode: ReleaseMode,
+}
+
+unsafe extern "C" fn release_schema(schema: *mut ArrowSchema) {
+    if schema.is_null() {
+        return;
+    }
+    let schema = &mut *schema;
+    if !schema.format.is_null() {
+        drop(std::ffi::CString::from_raw(schema.format as *mut c_char));
+    }
+    schema.release = None;
+}
+
+unsafe extern "C" fn release_array<T: ArrowPrimitive>(array: *mut ArrowArray) {
+    if array.is_null() {
+        return;
+    }
+    let array = &mut *array;
+    // Reclaim the two-element buffers array as the type it was actually allocated as
+    // (`Box<[*const c_void; 2]>`), not as `Box<*const c_void>` — the pointer types
+    // otherwise match, but the latter has half the size and frees with the wrong
+    // layout.
+    drop(Box::from_raw(array.buffers as *mut [*const c_void; 2]));
+
+    let exported = Box::from_raw(array.private_data as *mut ExportedArray<T>);
+    match exported.vm.attach_current_thread() {
+        Ok(env) => {
+            let res = T::release(
+                &env,
+                exported.global.as_obj(),
+                exported.ptr.as_ptr(),
+                exported.mode as i32,
+            );
+            if let Err(e) = res {
+                error!("error releasing exported arrow array: {:#?}", e);
+            }
+        }
+        Err(e) => error!(
+            "error attaching to JVM to release exported arrow array: {:#?}",
+            e
+        ),
+    }
+    array.release = None;
+}
+
+impl<'a, 'b, T: ArrowPrimitive> AutoArray<'a, 'b, T> {
+    /// Exports this array's pinned memory through the Arrow C Data Interface, filling
+    /// in the caller-provided `schema` and `array` structs.
+    ///
+    /// This method consumes `self`. `obj` is promoted to a global reference and the
+    /// originating `JavaVM` is retained so that `array`'s `release` callback can free
+    /// the underlying JNI array (via `Release<Type>ArrayElements`) correctly even if
+    /// the Arrow consumer calls it later, from a different or newly-attached thread,
+    /// after the native method that produced this export has already returned.
+    /// Nulling out `release` once it's run is the ABI invariant that tells Arrow
+    /// consumers the struct has been consumed; consumers must call `release` exactly
+    /// once.
+    ///
+    /// # Safety
+    ///
+    /// The caller must treat `schema` and `array` as opaque to Rust afterwards and
+    /// must not read from the exported buffer after calling `array.release`.
+    pub unsafe fn export_to_arrow(
+        self,
+        schema: &mut ArrowSchema,
+        array: &mut ArrowArray,
+    ) -> Result<()> {
+        let format = std::ffi::CString::new(T::ARROW_FORMAT).expect("format has no NUL bytes");
+        *schema = ArrowSchema {
+            format: format.into_raw(),
+            name: ptr::null(),
+            metadata: ptr::null(),
+            flags: 0,
+            n_children: 0,
+            children: ptr::null_mut(),
+            dictionary: ptr::null_mut(),
+            release: Some(release_schema),
+            private_data: ptr::null_mut(),
+        };
+
+        let length = self.size()? as i64;
+        let data_ptr = self.as_ptr() as *const c_void;
+        let buffers = Box::into_raw(Box::new([ptr::null(), data_ptr]));
+
+        let (env, obj, ptr, mode) = self.into_raw_parts();
+        let exported = ExportedArray {
+            global: env.new_global_ref(obj)?,
+            vm: env.get_java_vm()?,
+            ptr,
+            mode,
+        };
+
+        *array = ArrowArray {
+            length,
+            null_count: 0,
+            offset: 0,
+            n_buffers: 2,
+            n_children: 0,
+            buffers: buffers as *mut *const c_void,
+            children: ptr::null_mut(),
+            dictionary: ptr::null_mut(),
+            release: Some(release_array::<T>),
+            private_data: Box::into_raw(Box::new(exported)) as *mut c_void,
+        };
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::c_void;
+
+    // Regression test for reclaiming the `buffers` allocation as the two-element
+    // array it actually is, rather than as a single pointer (which has the wrong
+    // size and is undefined behavior to free). This exercises just the
+    // allocate/cast-back/drop pattern `release_array` uses, without requiring a JVM.
+    #[test]
+    fn buffers_box_roundtrips_through_erased_pointer() {
+        let a = 1usize as *const c_void;
+        let b = 2usize as *const c_void;
+        let boxed: *mut *const c_void = Box::into_raw(Box::new([a, b])) as *mut *const c_void;
+
+        let restored = unsafe { Box::from_raw(boxed as *mut [*const c_void; 2]) };
+        assert_eq!(*restored, [a, b]);
+    }
+}
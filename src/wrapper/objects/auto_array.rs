@@ -0,0 +1,159 @@
+use crate::sys::{jboolean, jsize};
+use log::error;
+
+use crate::objects::release_mode::ReleaseMode;
+use crate::objects::type_array::TypeArray;
+use crate::{errors::*, objects::JObject, sys, JNIEnv};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+use std::slice;
+
+/// Auto-release wrapper for pointer-based primitive arrays.
+///
+/// This wrapper is used to wrap pointers returned by `Get<Type>ArrayElements`.
+///
+/// These arrays need to be released through a call to `Release<Type>ArrayElements`.
+/// This wrapper provides automatic array release when it goes out of scope.
+pub struct AutoArray<'a: 'b, 'b, T: TypeArray> {
+    obj: JObject<'a>,
+    ptr: NonNull<T>,
+    mode: ReleaseMode,
+    is_copy: bool,
+    len: jsize,
+    env: &'b JNIEnv<'a>,
+}
+
+impl<'a, 'b, T: TypeArray> AutoArray<'a, 'b, T> {
+    /// Creates a new auto-release wrapper for a pointer-based primitive array
+    ///
+    /// Once this wrapper goes out of scope, `Release<Type>ArrayElements` will be
+    /// called on the object. While wrapped, the object can be accessed via
+    /// the `From` impl.
+    pub(crate) fn new(
+        env: &'b JNIEnv<'a>,
+        obj: JObject<'a>,
+        ptr: *mut T,
+        mode: ReleaseMode,
+        is_copy: bool,
+    ) -> Result<Self> {
+        let len = env.get_array_length(*obj)?;
+        Ok(AutoArray {
+            obj,
+            ptr: NonNull::new(ptr).ok_or(Error::NullPtr("Non-null ptr expected"))?,
+            mode,
+            is_copy,
+            len,
+            env,
+        })
+    }
+
+    /// Get a reference to the wrapped pointer
+    pub fn as_ptr(&self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    /// Decomposes the wrapper into its raw parts, without running `Drop` (and thus
+    /// without releasing the array).
+    ///
+    /// This is for crate-internal callers that need to take over responsibility for
+    /// eventually releasing the array themselves, e.g. to promote `obj` to a global
+    /// reference and release from a separate, possibly-reattached, JNI call.
+    pub(crate) fn into_raw_parts(self) -> (&'b JNIEnv<'a>, JObject<'a>, NonNull<T>, ReleaseMode) {
+        let me = std::mem::ManuallyDrop::new(self);
+        (me.env, me.obj, me.ptr, me.mode)
+    }
+
+    /// Commits the changes to the array, if it is a copy
+    pub fn commit(&mut self) -> Result<()> {
+        self.release_array_elements(sys::JNI_COMMIT)
+    }
+
+    fn release_array_elements(&mut self, mode: i32) -> Result<()> {
+        unsafe { T::release(self.env, self.obj, self.ptr.as_mut(), mode) }
+    }
+
+    /// Don't commit the changes to the array on release (if it is a copy).
+    /// This has no effect if the array is not a copy.
+    /// This method is useful to change the release mode of an array originally created
+    /// with `ReleaseMode::CopyBack`.
+    pub fn discard(&mut self) {
+        self.mode = ReleaseMode::NoCopyBack;
+    }
+
+    /// Indicates if the array is a copy or not
+    pub fn is_copy(&self) -> bool {
+        self.is_copy
+    }
+
+    /// Returns the array size, as cached when this wrapper was created.
+    ///
+    /// This does not make a JNI call: the array can't change size while a native
+    /// `Get<Type>ArrayElements` pointer to it is held, so the length is fetched once,
+    /// at construction, and cached here.
+    pub fn size(&self) -> Result<jsize> {
+        Ok(self.len)
+    }
+
+    /// Borrows the wrapped array as a slice of its elements.
+    ///
+    /// The slice's length is the array's cached length, and it borrows `self`, so no
+    /// release can occur while it is borrowed.
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len as usize) }
+    }
+
+    /// Borrows the wrapped array as a mutable slice of its elements.
+    ///
+    /// The slice's length is the array's cached length, and it borrows `self`, so no
+    /// release can occur while it is borrowed.
+    pub fn as_slice_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len as usize) }
+    }
+}
+
+impl<'a, 'b, T: TypeArray> Deref for AutoArray<'a, 'b, T> {
+    type Target = [T];
+
+    /// Dereferences into a slice of the array's elements.
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<'a, 'b, T: TypeArray> DerefMut for AutoArray<'a, 'b, T> {
+    /// Dereferences into a mutable slice of the array's elements.
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_slice_mut()
+    }
+}
+
+impl<'a, 'b, T: TypeArray> Drop for AutoArray<'a, 'b, T> {
+    fn drop(&mut self) {
+        let res = self.release_array_elements(self.mode as i32);
+        match res {
+            Ok(()) => {}
+            Err(e) => error!("error releasing array: {:#?}", e),
+        }
+    }
+}
+
+impl<'a, T: TypeArray> From<&'a AutoArray<'a, '_, T>> for *mut T {
+    fn from(other: &'a AutoArray<T>) -> *mut T {
+        other.as_ptr()
+    }
+}
+
+impl<'a> JNIEnv<'a> {
+    /// Get a pointer-based wrapper for the elements of a primitive array, to
+    /// be released via `Release<Type>ArrayElements` when the returned
+    /// `AutoArray` is dropped.
+    pub fn get_array_elements<'b, T: TypeArray>(
+        &'b self,
+        array: JObject<'a>,
+        mode: ReleaseMode,
+    ) -> Result<AutoArray<'a, 'b, T>> {
+        let mut is_copy: jboolean = 0xff;
+        let ptr = T::get(self, array, &mut is_copy)?;
+        AutoArray::new(self, array, ptr, mode, is_copy == sys::JNI_TRUE)
+    }
+}
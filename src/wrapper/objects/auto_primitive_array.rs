@@ -0,0 +1,138 @@
+use log::error;
+
+use crate::objects::release_mode::ReleaseMode;
+use crate::objects::type_array::TypeArray;
+use crate::{errors::*, objects::JObject, sys, JNIEnv};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+/// Auto-release wrapper for a critical array, obtained via
+/// `GetPrimitiveArrayCritical`.
+///
+/// Compared to [`AutoArray`](super::auto_array::AutoArray), this wrapper is more
+/// likely to get a direct pointer to the underlying array: the JVM is permitted (but
+/// not required) to pin the memory and hand back a direct pointer to it, which is why
+/// the access is called "critical". The JVM may still return a copy; [`is_copy`]
+/// reports which one was returned.
+///
+/// [`is_copy`]: AutoPrimitiveArray::is_copy
+///
+/// # Critical region restrictions
+///
+/// Per the JNI specification, between the call to `GetPrimitiveArrayCritical` and the
+/// matching call to `ReleasePrimitiveArrayCritical`, native code must not call *any*
+/// other JNI function, nor perform any operation that may block (such as acquiring a
+/// lock that's also taken by another thread inside a critical region) or depend on the
+/// current thread yielding to the JVM. Failing to observe this restriction may deadlock
+/// or crash the JVM.
+///
+/// This is **not** enforced by the type system: `get_primitive_array_critical` takes
+/// `&JNIEnv`, like every other JNI-calling method on `JNIEnv`, so nothing stops the
+/// same shared reference from being used to acquire a second critical region, or to
+/// make any other JNI call, while this one is still alive. Upholding the restriction
+/// above is the caller's responsibility.
+pub struct AutoPrimitiveArray<'a: 'b, 'b, T: TypeArray> {
+    obj: JObject<'a>,
+    ptr: NonNull<T>,
+    mode: ReleaseMode,
+    is_copy: bool,
+    len: sys::jsize,
+    env: &'b JNIEnv<'a>,
+    _marker: PhantomData<&'b mut T>,
+}
+
+impl<'a, 'b, T: TypeArray> AutoPrimitiveArray<'a, 'b, T> {
+    /// Creates a new auto-release wrapper for a critical array.
+    ///
+    /// Once this wrapper goes out of scope, `ReleasePrimitiveArrayCritical` will be
+    /// called on the object.
+    ///
+    /// `len` must have been fetched via `GetArrayLength` *before* entering the
+    /// critical region (i.e. before `ptr` was obtained), since `size()` must not make
+    /// a JNI call while the critical region is held.
+    pub(crate) fn new(
+        env: &'b JNIEnv<'a>,
+        obj: JObject<'a>,
+        ptr: *mut T,
+        mode: ReleaseMode,
+        is_copy: bool,
+        len: sys::jsize,
+    ) -> Result<Self> {
+        Ok(AutoPrimitiveArray {
+            obj,
+            ptr: NonNull::new(ptr).ok_or(Error::NullPtr("Non-null ptr expected"))?,
+            mode,
+            is_copy,
+            len,
+            env,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Get a reference to the wrapped pointer
+    pub fn as_ptr(&self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    fn release_primitive_array_critical(&mut self, mode: i32) -> Result<()> {
+        jni_void_call!(
+            self.env.get_native_interface(),
+            ReleasePrimitiveArrayCritical,
+            *self.obj,
+            self.ptr.as_mut() as *mut T as *mut std::ffi::c_void,
+            mode
+        );
+        Ok(())
+    }
+
+    /// Don't commit the changes to the array on release (if it is a copy).
+    /// This has no effect if the array is not a copy.
+    pub fn discard(&mut self) {
+        self.mode = ReleaseMode::NoCopyBack;
+    }
+
+    /// Indicates if the array is a copy or not
+    pub fn is_copy(&self) -> bool {
+        self.is_copy
+    }
+
+    /// Returns the array size, as cached at acquisition time.
+    ///
+    /// This does not make a JNI call: while the critical region is held, the spec
+    /// forbids calling any JNI function (including `GetArrayLength`), so the length
+    /// is fetched once, before the region is entered, and cached here.
+    pub fn size(&self) -> Result<sys::jsize> {
+        Ok(self.len)
+    }
+}
+
+impl<'a, 'b, T: TypeArray> Drop for AutoPrimitiveArray<'a, 'b, T> {
+    fn drop(&mut self) {
+        let res = self.release_primitive_array_critical(self.mode as i32);
+        match res {
+            Ok(()) => {}
+            Err(e) => error!("error releasing primitive array critical: {:#?}", e),
+        }
+    }
+}
+
+impl<'a> JNIEnv<'a> {
+    /// Get a critical, zero-copy wrapper for the elements of a primitive array, via
+    /// `GetPrimitiveArrayCritical`. See [`AutoPrimitiveArray`] for the restrictions
+    /// that apply while the returned wrapper is alive.
+    pub fn get_primitive_array_critical<'b, T: TypeArray>(
+        &'b self,
+        array: JObject<'a>,
+        mode: ReleaseMode,
+    ) -> Result<AutoPrimitiveArray<'a, 'b, T>> {
+        // Fetched *before* entering the critical region: `GetArrayLength` is a JNI
+        // call, and none may be made once inside it.
+        let len = self.get_array_length(*array)?;
+
+        let mut is_copy: sys::jboolean = 0xff;
+        let internal = self.get_native_interface();
+        let ptr = jni_non_null_call!(internal, GetPrimitiveArrayCritical, *array, &mut is_copy)
+            as *mut T;
+        AutoPrimitiveArray::new(self, array, ptr, mode, is_copy == sys::JNI_TRUE, len)
+    }
+}
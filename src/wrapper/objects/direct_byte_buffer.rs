@@ -0,0 +1,134 @@
+use crate::{errors::*, objects::JObject, sys, JNIEnv};
+use std::os::raw::c_void;
+use std::sync::{Arc, Mutex};
+
+/// The raw allocation info needed to reconstruct and free a `Vec<u8>` that was handed
+/// to the JVM via `NewDirectByteBuffer`.
+struct Reclamation {
+    ptr: *mut u8,
+    len: usize,
+    cap: usize,
+}
+
+// SAFETY: `Reclamation` is only ever reclaimed once, guarded by the `Mutex` it's
+// stored behind, so it's fine to move it across threads.
+unsafe impl Send for Reclamation {}
+
+impl Reclamation {
+    unsafe fn reclaim(self) {
+        drop(Vec::from_raw_parts(self.ptr, self.len, self.cap));
+    }
+}
+
+/// A `java.nio.ByteBuffer` created via `NewDirectByteBuffer` over memory owned by
+/// Rust, so that large buffers can be handed to Java without the copy that
+/// `byte_array_from_slice` requires.
+///
+/// The JNI spec gives no callback when Java garbage-collects a direct
+/// `ByteBuffer`. The usual calling convention for `new_direct_byte_buffer_owned` is
+/// to build the buffer, hand `as_obj()` back to Java as a native method's return
+/// value, and let this wrapper drop right there — which is exactly when Java is just
+/// starting to use the buffer. So dropping `DirectByteBuffer` does **not** free the
+/// backing allocation; the only way to free it is to attach a
+/// [`DirectByteBufferCleanup`] handle (via [`cleanup_handle`](Self::cleanup_handle))
+/// to a Java-side `java.lang.ref.Cleaner` or `PhantomReference` and let it run once
+/// Java has confirmed the `ByteBuffer` is unreachable. Forgetting to attach one leaks
+/// the allocation; that's the tradeoff for not risking a use-after-free on the
+/// common path.
+pub struct DirectByteBuffer<'a> {
+    obj: JObject<'a>,
+    reclamation: Arc<Mutex<Option<Reclamation>>>,
+}
+
+impl<'a> DirectByteBuffer<'a> {
+    fn new(obj: JObject<'a>, reclamation: Arc<Mutex<Option<Reclamation>>>) -> Self {
+        DirectByteBuffer { obj, reclamation }
+    }
+
+    /// Returns the wrapped `ByteBuffer` object.
+    pub fn as_obj(&self) -> JObject<'a> {
+        self.obj
+    }
+
+    /// Returns a `Send`-able handle that reclaims the backing allocation when run.
+    /// Give this to whatever mechanism observes the Java-side `ByteBuffer` becoming
+    /// unreachable (a `Cleaner` action, a `PhantomReference` queue processor, ...);
+    /// this is the only thing that frees the allocation.
+    pub fn cleanup_handle(&self) -> DirectByteBufferCleanup {
+        DirectByteBufferCleanup {
+            reclamation: self.reclamation.clone(),
+        }
+    }
+}
+
+/// A handle that frees a [`DirectByteBuffer`]'s backing allocation exactly once,
+/// when [`run`](Self::run) is called. Dropping a `DirectByteBuffer` itself never
+/// frees the allocation, so this handle (driven by Java confirming the `ByteBuffer`
+/// is unreachable) is the only path that does.
+pub struct DirectByteBufferCleanup {
+    reclamation: Arc<Mutex<Option<Reclamation>>>,
+}
+
+impl DirectByteBufferCleanup {
+    /// Frees the backing allocation, if it hasn't been freed already.
+    pub fn run(self) {
+        if let Some(r) = self.reclamation.lock().unwrap().take() {
+            unsafe { r.reclaim() }
+        }
+    }
+}
+
+impl<'a> JNIEnv<'a> {
+    /// Creates a `java.nio.ByteBuffer` backed by `data`, transferring ownership of
+    /// the allocation to the returned [`DirectByteBuffer`]. See that type's docs for
+    /// how the backing memory gets reclaimed, since the JVM gives no GC callback for
+    /// direct buffers.
+    pub fn new_direct_byte_buffer_owned(&self, mut data: Vec<u8>) -> Result<DirectByteBuffer<'a>> {
+        let ptr = data.as_mut_ptr();
+        let len = data.len();
+        let cap = data.capacity();
+
+        let internal = self.get_native_interface();
+        // `data` stays a plain `Vec` until the call succeeds, so if
+        // `NewDirectByteBuffer` returns NULL and `jni_non_null_call!` early-returns
+        // `Err`, the ordinary `Vec` drop below reclaims the allocation instead of
+        // leaking it.
+        let obj = jni_non_null_call!(
+            internal,
+            NewDirectByteBuffer,
+            ptr as *mut c_void,
+            len as sys::jlong
+        );
+
+        // The call succeeded: the JVM now holds a pointer into `data`, so forget it
+        // instead of letting it drop, and hand the raw parts to `Reclamation`.
+        std::mem::forget(data);
+        let reclamation = Arc::new(Mutex::new(Some(Reclamation { ptr, len, cap })));
+        Ok(DirectByteBuffer::new(JObject::from(obj), reclamation))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Reclamation;
+
+    // Regression test for the shared take-at-most-once guard that
+    // `DirectByteBufferCleanup::run` relies on to never double-free the backing
+    // allocation, independent of JNI.
+    #[test]
+    fn reclamation_is_taken_at_most_once() {
+        let mut data = std::mem::ManuallyDrop::new(vec![1u8, 2, 3]);
+        let reclamation = std::sync::Mutex::new(Some(Reclamation {
+            ptr: data.as_mut_ptr(),
+            len: data.len(),
+            cap: data.capacity(),
+        }));
+
+        let first = reclamation.lock().unwrap().take();
+        assert!(first.is_some());
+        unsafe { first.unwrap().reclaim() };
+
+        let second = reclamation.lock().unwrap().take();
+        assert!(second.is_none());
+    }
+}
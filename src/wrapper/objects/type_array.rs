@@ -0,0 +1,50 @@
+use crate::{errors::*, objects::JObject, sys::jboolean, JNIEnv};
+
+/// A primitive type that can be accessed via `Get<Type>ArrayElements` /
+/// `Release<Type>ArrayElements`. This trait is implemented for `jboolean`,
+/// `jbyte`, `jchar`, `jshort`, `jint`, `jlong`, `jfloat`, and `jdouble`, and
+/// is what allows `AutoArray` to be generic over all of them instead of
+/// having a separate wrapper type per primitive.
+pub trait TypeArray: Sized {
+    /// Fetches a pointer to the elements of the array, via the appropriate
+    /// `Get<Type>ArrayElements` call for this type.
+    fn get(env: &JNIEnv, obj: JObject, is_copy: &mut jboolean) -> Result<*mut Self>;
+
+    /// Releases the array back to the JVM, via the appropriate
+    /// `Release<Type>ArrayElements` call for this type.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a pointer previously returned by `get` for the same
+    /// `obj` and must not have already been released.
+    unsafe fn release(env: &JNIEnv, obj: JObject, ptr: *mut Self, mode: i32) -> Result<()>;
+}
+
+macro_rules! type_array {
+    ($jni_type:ty, $jni_get:ident, $jni_release:ident) => {
+        impl TypeArray for $jni_type {
+            fn get(env: &JNIEnv, obj: JObject, is_copy: &mut jboolean) -> Result<*mut Self> {
+                let internal = env.get_native_interface();
+                Ok(jni_non_null_call!(internal, $jni_get, *obj, is_copy))
+            }
+
+            unsafe fn release(env: &JNIEnv, obj: JObject, ptr: *mut Self, mode: i32) -> Result<()> {
+                jni_void_call!(env.get_native_interface(), $jni_release, *obj, ptr, mode);
+                Ok(())
+            }
+        }
+    };
+}
+
+type_array!(
+    crate::sys::jboolean,
+    GetBooleanArrayElements,
+    ReleaseBooleanArrayElements
+);
+type_array!(crate::sys::jbyte, GetByteArrayElements, ReleaseByteArrayElements);
+type_array!(crate::sys::jchar, GetCharArrayElements, ReleaseCharArrayElements);
+type_array!(crate::sys::jshort, GetShortArrayElements, ReleaseShortArrayElements);
+type_array!(crate::sys::jint, GetIntArrayElements, ReleaseIntArrayElements);
+type_array!(crate::sys::jlong, GetLongArrayElements, ReleaseLongArrayElements);
+type_array!(crate::sys::jfloat, GetFloatArrayElements, ReleaseFloatArrayElements);
+type_array!(crate::sys::jdouble, GetDoubleArrayElements, ReleaseDoubleArrayElements);
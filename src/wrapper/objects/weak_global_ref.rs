@@ -0,0 +1,81 @@
+use log::error;
+
+use crate::{errors::*, objects::GlobalRef, objects::JObject, sys, JNIEnv, JavaVM};
+
+/// A weak global JVM reference, created via `NewWeakGlobalRef`.
+///
+/// Unlike [`GlobalRef`], a `WeakGlobalRef` doesn't keep its referent alive, so it is
+/// safe to store in caches or back-pointers that shouldn't pin the rest of the object
+/// graph in memory. Because the referent may be collected at any time, it can't be
+/// used directly; call [`upgrade`](WeakGlobalRef::upgrade) to get a strong
+/// [`GlobalRef`], or `None` if the object has already been collected.
+///
+/// Like `GlobalRef`, this is valid across threads and JNI calls, and the weak
+/// reference is freed via `DeleteWeakGlobalRef` when it is dropped.
+pub struct WeakGlobalRef {
+    obj: sys::jobject,
+    vm: JavaVM,
+}
+
+impl WeakGlobalRef {
+    pub(crate) fn new(vm: JavaVM, obj: sys::jobject) -> Self {
+        WeakGlobalRef { obj, vm }
+    }
+
+    /// Attempts to promote this weak reference to a strong [`GlobalRef`].
+    ///
+    /// Returns `Ok(None)` if the referent has already been garbage-collected.
+    pub fn upgrade(&self, env: &JNIEnv) -> Result<Option<GlobalRef>> {
+        let internal = env.get_native_interface();
+
+        // `NewLocalRef` legitimately returns NULL exactly when the referent has
+        // already been collected -- the one case this method exists to detect -- so,
+        // unlike most calls in this crate, we can't treat a NULL return as an error
+        // here (that's what `jni_non_null_call!` would do).
+        let local = unsafe { (**internal).NewLocalRef.unwrap()(internal, self.obj) };
+        if local.is_null() {
+            return Ok(None);
+        }
+        let local = JObject::from(local);
+
+        // Promote to a global ref, then delete the local one either way -- otherwise
+        // every `upgrade()` call (the whole point of caches/registries that poll this
+        // repeatedly) leaks a local ref until the native frame returns to Java.
+        let global = env.new_global_ref(local);
+        env.delete_local_ref(local)?;
+
+        Ok(Some(global?))
+    }
+}
+
+impl Drop for WeakGlobalRef {
+    fn drop(&mut self) {
+        match self.vm.get_env() {
+            Ok(env) => {
+                jni_void_call!(env.get_native_interface(), DeleteWeakGlobalRef, self.obj);
+            }
+            // Dropped on a thread that isn't attached: attach it so the weak
+            // reference is still deleted.
+            Err(_) => match self.vm.attach_current_thread() {
+                Ok(env) => {
+                    jni_void_call!(env.get_native_interface(), DeleteWeakGlobalRef, self.obj);
+                }
+                Err(e) => error!(
+                    "error attaching to JVM thread to delete weak global ref: {:#?}",
+                    e
+                ),
+            },
+        }
+    }
+}
+
+impl<'a> JNIEnv<'a> {
+    /// Creates a new weak global reference to `obj`, via `NewWeakGlobalRef`. See
+    /// [`WeakGlobalRef`] for how to safely access the referent once it's been made
+    /// weak.
+    pub fn new_weak_global_ref(&self, obj: JObject) -> Result<WeakGlobalRef> {
+        let internal = self.get_native_interface();
+        let weak = jni_non_null_call!(internal, NewWeakGlobalRef, *obj);
+        Ok(WeakGlobalRef::new(self.get_java_vm()?, weak))
+    }
+}
@@ -0,0 +1,60 @@
+//! Integration test for exporting a JNI primitive array through the Arrow C Data
+//! Interface. Requires a live JVM, like the rest of this crate's integration tests
+//! (run with the `invocation` feature, which links an embedded JVM via
+//! `JNI_CreateJavaVM`).
+
+use jni::objects::arrow::{ArrowArray, ArrowSchema};
+use jni::objects::release_mode::ReleaseMode;
+use jni::sys::jbyte;
+use jni::{InitArgsBuilder, JavaVM};
+
+fn jvm() -> JavaVM {
+    let args = InitArgsBuilder::new()
+        .build()
+        .expect("invalid JVM init args");
+    JavaVM::new(args).expect("failed to create a JVM for testing")
+}
+
+/// Exercises `export_to_arrow`/`release_array` themselves (not just the
+/// allocate/cast-back/drop pattern they're built from), so a future regression in the
+/// `buffers` box layout or the global-ref/reattach release path is caught here.
+#[test]
+fn export_to_arrow_round_trips_through_release() {
+    let vm = jvm();
+    let env = vm.attach_current_thread().expect("attach_current_thread");
+
+    let data: [jbyte; 4] = [1, 2, 3, 4];
+    let array_obj = env
+        .new_byte_array(data.len() as i32)
+        .expect("new_byte_array");
+    env.set_byte_array_region(array_obj, 0, &data)
+        .expect("set_byte_array_region");
+
+    let auto_array = env
+        .get_array_elements::<jbyte>(array_obj.into(), ReleaseMode::NoCopyBack)
+        .expect("get_array_elements");
+
+    let mut schema = unsafe { std::mem::zeroed::<ArrowSchema>() };
+    let mut arrow_array = unsafe { std::mem::zeroed::<ArrowArray>() };
+    unsafe {
+        auto_array
+            .export_to_arrow(&mut schema, &mut arrow_array)
+            .expect("export_to_arrow");
+    }
+
+    assert_eq!(arrow_array.length, data.len() as i64);
+    assert_eq!(arrow_array.n_buffers, 2);
+    assert!(arrow_array.release.is_some());
+    assert!(schema.release.is_some());
+
+    // Release through the struct's own callbacks, exactly as an Arrow consumer
+    // would: this is what actually runs `release_array`'s buffers-box reclaim and
+    // `Release<Type>ArrayElements` call.
+    unsafe {
+        (arrow_array.release.unwrap())(&mut arrow_array);
+        (schema.release.unwrap())(&mut schema);
+    }
+
+    assert!(arrow_array.release.is_none());
+    assert!(schema.release.is_none());
+}